@@ -0,0 +1,61 @@
+// SPDX-License-Identifier: GPL-2.0
+
+//! Device resource management.
+//!
+//! C header: [`include/linux/device.h`](../../../../include/linux/device.h)
+
+use core::ffi::c_void;
+
+use crate::{
+    bindings,
+    device::RawDevice,
+    error::{Error, Result},
+    prelude::*,
+};
+
+/// Holds a Rust value together with the action to run on device unbind.
+struct Devres<T> {
+    data: T,
+    action: fn(T),
+}
+
+impl<T> Devres<T> {
+    /// Consumes the resource and runs its cleanup action.
+    fn run(self) {
+        (self.action)(self.data);
+    }
+}
+
+/// Registers `action` to be run with `data` when `dev` is unbound.
+///
+/// The value and its action are boxed and a trampoline is registered with `devm_add_action`. If
+/// registration fails, `action` is run immediately before returning the error, mirroring
+/// `devm_add_action_or_reset`.
+pub fn devm_add_action<D: RawDevice, T>(dev: &D, data: T, action: fn(T)) -> Result {
+    let boxed = Box::try_new(Devres { data, action })?;
+    let ptr = Box::into_raw(boxed);
+
+    // SAFETY: `dev.raw_device()` is valid by the contract of [`RawDevice`]. `ptr` was just
+    // produced by `Box::into_raw`, so it is a valid, uniquely-owned pointer that the trampoline
+    // reclaims exactly once.
+    let ret = unsafe {
+        bindings::devm_add_action(dev.raw_device(), Some(trampoline::<T>), ptr as *mut c_void)
+    };
+    if ret < 0 {
+        // Registration failed: run the action now and release the box, mirroring
+        // `devm_add_action_or_reset`.
+        // SAFETY: `ptr` is still owned here since the trampoline was never registered.
+        let boxed = unsafe { Box::from_raw(ptr) };
+        boxed.run();
+        return Err(Error::from_kernel_errno(ret));
+    }
+
+    Ok(())
+}
+
+unsafe extern "C" fn trampoline<T>(ptr: *mut c_void) {
+    // SAFETY: `ptr` was produced by `Box::into_raw` in `devm_add_action` and is handed back to us
+    // exactly once by the device core.
+    let boxed = unsafe { Box::from_raw(ptr as *mut Devres<T>) };
+    boxed.run();
+}
@@ -6,9 +6,17 @@
 //!
 //! Reference: <https://docs.kernel.org/driver-api/firmware/request_firmware.html>
 
+use core::ffi::c_void;
 use core::{ptr, slice};
 
-use crate::{device::Device, error::Result, str::CStr, to_result};
+use crate::{
+    device::Device,
+    error::{Error, Result},
+    prelude::*,
+    str::CStr,
+    to_result,
+    ThisModule,
+};
 
 /// Represents firmware data.
 ///
@@ -58,6 +66,51 @@ impl Firmware {
         Ok(unsafe { Self::new(ptr) })
     }
 
+    /// Sends a firmware request and schedules `callback` to run once loading completes.
+    ///
+    /// Unlike [`request`](Self::request), this returns immediately and does not block, so it can
+    /// be used to kick off firmware retrieval during probe. `callback` is invoked later with the
+    /// loaded [`Firmware`] on success, or an error if the image could not be obtained; the
+    /// [`Firmware`] it receives releases its data through the usual [`Drop`] when dropped.
+    ///
+    /// `module` must be the module owning the driver, so that it stays loaded until the request
+    /// completes. `name` is used as `$FIRMWARE` in the uevent environment.
+    pub fn request_nowait<F>(
+        module: &'static ThisModule,
+        name: &CStr,
+        device: &Device,
+        callback: F,
+    ) -> Result
+    where
+        F: FnOnce(Result<Firmware>) + Send + 'static,
+    {
+        let boxed = Box::try_new(callback)?;
+        let ptr = Box::into_raw(boxed);
+
+        // SAFETY: `module.0` lives as long as the module, `name` and `device` are valid for the
+        // duration of the call, and `ptr` is a freshly boxed callback handed to the completion
+        // trampoline exactly once.
+        let ret = unsafe {
+            bindings::request_firmware_nowait(
+                module.0,
+                true,
+                name.as_char_ptr(),
+                device.ptr,
+                bindings::GFP_KERNEL,
+                ptr as *mut c_void,
+                Some(request_nowait_callback::<F>),
+            )
+        };
+        if ret != 0 {
+            // The completion trampoline will not run, so reclaim the callback here.
+            // SAFETY: `ptr` was produced by `Box::into_raw` above and is still owned.
+            drop(unsafe { Box::from_raw(ptr) });
+            return Err(Error::from_kernel_errno(ret));
+        }
+
+        Ok(())
+    }
+
     /// Creates a new firmware from the given pointer.
     ///
     /// # Safety
@@ -74,6 +127,23 @@ impl Firmware {
     }
 }
 
+unsafe extern "C" fn request_nowait_callback<F>(
+    fw: *const bindings::firmware,
+    context: *mut c_void,
+) where
+    F: FnOnce(Result<Firmware>) + Send + 'static,
+{
+    // SAFETY: `context` is the callback boxed in `request_nowait`, handed back to us exactly once.
+    let callback = unsafe { Box::from_raw(context as *mut F) };
+    let res = if fw.is_null() {
+        Err(ENOENT)
+    } else {
+        // SAFETY: `fw` is non-null and ownership is transferred to us by the firmware core.
+        Ok(unsafe { Firmware::new(fw) })
+    };
+    callback(res);
+}
+
 impl Drop for Firmware {
     fn drop(&mut self) {
         unsafe { bindings::release_firmware(self.ptr) };
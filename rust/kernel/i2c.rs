@@ -10,13 +10,15 @@
 #![allow(dead_code)]
 
 use core::ffi::c_void;
+use core::marker::PhantomData;
 
 use crate::{
     bindings,
     device::{self, RawDevice},
     driver,
-    error::{from_kernel_result, Result},
+    error::{from_kernel_result, Error, Result},
     of,
+    prelude::*,
     str::{BStr, CStr},
     to_result,
     types::PointerWrapper,
@@ -280,7 +282,7 @@ impl Client {
     }
 
     /// Returns the raw `struct i2c_client` related to `self`.
-    unsafe fn raw_i2c_client(&self) -> *mut bindings::i2c_client {
+    pub(crate) unsafe fn raw_i2c_client(&self) -> *mut bindings::i2c_client {
         self.ptr
     }
 
@@ -291,6 +293,172 @@ impl Client {
         // SAFETY: By the type invariants, we know that `self.ptr` is non-null and valid.
         unsafe { (*self.ptr).addr }
     }
+
+    /// Reads a single byte from a device, from a designated register.
+    ///
+    /// Forwards to `i2c_smbus_read_byte_data`.
+    pub fn smbus_read_byte_data(&self, command: u8) -> Result<u8> {
+        // SAFETY: By the type invariants, `self.ptr` is non-null and valid.
+        let ret = unsafe { bindings::i2c_smbus_read_byte_data(self.ptr, command) };
+        if ret < 0 {
+            return Err(Error::from_kernel_errno(ret));
+        }
+        Ok(ret as u8)
+    }
+
+    /// Writes a single byte to a device, to a designated register.
+    ///
+    /// Forwards to `i2c_smbus_write_byte_data`.
+    pub fn smbus_write_byte_data(&self, command: u8, value: u8) -> Result {
+        // SAFETY: By the type invariants, `self.ptr` is non-null and valid.
+        to_result(unsafe { bindings::i2c_smbus_write_byte_data(self.ptr, command, value) })
+    }
+
+    /// Reads a 16-bit word from a device, from a designated register.
+    ///
+    /// Forwards to `i2c_smbus_read_word_data`.
+    pub fn smbus_read_word_data(&self, command: u8) -> Result<u16> {
+        // SAFETY: By the type invariants, `self.ptr` is non-null and valid.
+        let ret = unsafe { bindings::i2c_smbus_read_word_data(self.ptr, command) };
+        if ret < 0 {
+            return Err(Error::from_kernel_errno(ret));
+        }
+        Ok(ret as u16)
+    }
+
+    /// Writes a 16-bit word to a device, to a designated register.
+    ///
+    /// Forwards to `i2c_smbus_write_word_data`.
+    pub fn smbus_write_word_data(&self, command: u8, value: u16) -> Result {
+        // SAFETY: By the type invariants, `self.ptr` is non-null and valid.
+        to_result(unsafe { bindings::i2c_smbus_write_word_data(self.ptr, command, value) })
+    }
+
+    /// Reads a block of bytes from a device, from a designated register.
+    ///
+    /// Up to [`bindings::I2C_SMBUS_BLOCK_MAX`] bytes are read into `values`. Returns the number of
+    /// bytes actually read. Forwards to `i2c_smbus_read_i2c_block_data`.
+    pub fn smbus_read_block_data(&self, command: u8, values: &mut [u8]) -> Result<usize> {
+        let len = values.len().min(bindings::I2C_SMBUS_BLOCK_MAX as usize);
+        // SAFETY: By the type invariants, `self.ptr` is non-null and valid. `values` is valid for
+        // writes of at least `len` bytes.
+        let ret = unsafe {
+            bindings::i2c_smbus_read_i2c_block_data(
+                self.ptr,
+                command,
+                len as _,
+                values.as_mut_ptr(),
+            )
+        };
+        if ret < 0 {
+            return Err(Error::from_kernel_errno(ret));
+        }
+        Ok(ret as usize)
+    }
+
+    /// Writes a block of bytes to a device, to a designated register.
+    ///
+    /// Up to [`bindings::I2C_SMBUS_BLOCK_MAX`] bytes from `values` are written. Forwards to
+    /// `i2c_smbus_write_i2c_block_data`.
+    pub fn smbus_write_block_data(&self, command: u8, values: &[u8]) -> Result {
+        let len = values.len().min(bindings::I2C_SMBUS_BLOCK_MAX as usize);
+        // SAFETY: By the type invariants, `self.ptr` is non-null and valid. `values` is valid for
+        // reads of at least `len` bytes.
+        to_result(unsafe {
+            bindings::i2c_smbus_write_i2c_block_data(
+                self.ptr,
+                command,
+                len as _,
+                values.as_ptr(),
+            )
+        })
+    }
+
+    /// Performs a raw sequence of I2C master transfers.
+    ///
+    /// Each message in `msgs` is executed in order on the adapter the client is attached to.
+    /// Returns [`EIO`] unless all messages were transferred. Forwards to `i2c_transfer`.
+    pub fn transfer(&self, msgs: &[I2cMsg<'_>]) -> Result {
+        let mut raw = Vec::try_with_capacity(msgs.len())?;
+        for msg in msgs {
+            // INVARIANT: each message keeps borrowing its buffer for the duration of the call.
+            raw.try_push(msg.to_raw())?;
+        }
+
+        // SAFETY: By the type invariants, `self.ptr` is non-null and valid, so its `adapter` is a
+        // valid adapter. `raw` is a valid array of `raw.len()` messages, each pointing at a buffer
+        // that stays alive for the duration of the call.
+        let ret = unsafe {
+            bindings::i2c_transfer((*self.ptr).adapter, raw.as_mut_ptr(), raw.len() as _)
+        };
+        if ret < 0 {
+            return Err(Error::from_kernel_errno(ret));
+        }
+        if ret as usize != msgs.len() {
+            return Err(EIO);
+        }
+        Ok(())
+    }
+}
+
+/// Direction of an [`I2cMsg`].
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum I2cMsgFlags {
+    /// The message reads from the device into its buffer.
+    Read,
+    /// The message writes its buffer to the device.
+    Write,
+}
+
+/// A single message in a raw I2C master transfer.
+///
+/// # Invariants
+///
+/// `buf`/`len` describe a buffer that is borrowed for the lifetime `'a`, writable when `flags` is
+/// [`I2cMsgFlags::Read`].
+pub struct I2cMsg<'a> {
+    addr: u16,
+    flags: I2cMsgFlags,
+    buf: *mut u8,
+    len: u16,
+    _p: PhantomData<&'a mut [u8]>,
+}
+
+impl<'a> I2cMsg<'a> {
+    /// Builds a message that writes `buf` to the device at `addr`.
+    pub fn write(addr: u16, buf: &'a [u8]) -> Self {
+        Self {
+            addr,
+            flags: I2cMsgFlags::Write,
+            buf: buf.as_ptr() as *mut u8,
+            len: buf.len() as u16,
+            _p: PhantomData,
+        }
+    }
+
+    /// Builds a message that reads from the device at `addr` into `buf`.
+    pub fn read(addr: u16, buf: &'a mut [u8]) -> Self {
+        Self {
+            addr,
+            flags: I2cMsgFlags::Read,
+            buf: buf.as_mut_ptr(),
+            len: buf.len() as u16,
+            _p: PhantomData,
+        }
+    }
+
+    fn to_raw(&self) -> bindings::i2c_msg {
+        let flags = match self.flags {
+            I2cMsgFlags::Read => bindings::I2C_M_RD as u16,
+            I2cMsgFlags::Write => 0,
+        };
+        bindings::i2c_msg {
+            addr: self.addr,
+            flags,
+            len: self.len,
+            buf: self.buf,
+        }
+    }
 }
 
 // SAFETY: The device returned by `raw_device` is the raw platform device.
@@ -0,0 +1,418 @@
+// SPDX-License-Identifier: GPL-2.0
+
+//! Platform devices and drivers.
+//!
+//! C header: [`include/linux/platform_device.h`](../../../../include/linux/platform_device.h)
+
+#![allow(dead_code)]
+
+use core::ffi::c_void;
+
+use crate::{
+    bindings,
+    device::{self, RawDevice},
+    driver,
+    error::{from_kernel_err_ptr, from_kernel_result, Error, Result},
+    of,
+    prelude::*,
+    str::CStr,
+    to_result,
+    types::PointerWrapper,
+    ThisModule,
+};
+
+/// A registration of a platform driver.
+pub type DriverRegistration<T> = driver::Registration<DriverAdapter<T>>;
+
+/// An adapter for the registration of platform drivers.
+pub struct DriverAdapter<T: Driver>(T);
+
+impl<T: Driver> driver::DriverOps for DriverAdapter<T> {
+    type RegType = bindings::platform_driver;
+
+    unsafe fn register(
+        reg: *mut bindings::platform_driver,
+        name: &'static CStr,
+        module: &'static ThisModule,
+    ) -> Result {
+        // SAFETY: By the safety requirements of this function (defined in the trait definition),
+        // `reg` is non-null and valid.
+        let pdrv = unsafe { &mut *reg };
+
+        pdrv.driver.name = name.as_char_ptr();
+        pdrv.probe = Some(Self::probe_callback);
+        pdrv.remove = Some(Self::remove_callback);
+        if let Some(t) = T::OF_DEVICE_ID_TABLE {
+            pdrv.driver.of_match_table = t.as_ref();
+        }
+
+        // SAFETY:
+        //   - `pdrv` lives at least until the call to `platform_driver_unregister()` returns.
+        //   - `name` pointer has static lifetime.
+        //   - `module.0` lives at least as long as the module.
+        //   - `probe()` and `remove()` are static functions.
+        //   - `of_match_table` is either a raw pointer with static lifetime,
+        //      as guaranteed by the [`driver::IdTable`] type, or null.
+        to_result(unsafe { bindings::__platform_driver_register(reg, module.0) })
+    }
+
+    unsafe fn unregister(reg: *mut bindings::platform_driver) {
+        // SAFETY: By the safety requirements of this function (defined in the trait definition),
+        // `reg` was passed (and updated) by a previous successful call to
+        // `__platform_driver_register`.
+        unsafe { bindings::platform_driver_unregister(reg) };
+    }
+}
+
+impl<T: Driver> DriverAdapter<T> {
+    fn get_id_info(dev: &Device) -> Option<&'static T::IdInfo> {
+        let table = T::OF_DEVICE_ID_TABLE?;
+
+        // SAFETY: `table` has static lifetime, so it is valid for read. `dev` is guaranteed to be
+        // valid while it's alive, so is the raw device returned by it.
+        let id = unsafe { bindings::of_match_device(table.as_ref(), dev.raw_device()) };
+        if id.is_null() {
+            return None;
+        }
+
+        // SAFETY: `id` is a pointer within the static table, so it's always valid.
+        let offset = unsafe { (*id).data };
+        if offset.is_null() {
+            return None;
+        }
+
+        // SAFETY: The offset comes from a previous call to `offset_from` in `IdArray::new`, which
+        // guarantees that the resulting pointer is within the table.
+        let ptr = unsafe {
+            id.cast::<u8>()
+                .offset(offset as _)
+                .cast::<Option<T::IdInfo>>()
+        };
+
+        // SAFETY: The id table has a static lifetime, so `ptr` is guaranteed to be valid for read.
+        unsafe { (&*ptr).as_ref() }
+    }
+
+    extern "C" fn probe_callback(pdev: *mut bindings::platform_device) -> core::ffi::c_int {
+        from_kernel_result! {
+            // SAFETY: `pdev` is valid by the contract with the C code. `dev` is alive only for the
+            // duration of this call, so it is guaranteed to remain alive for the lifetime of
+            // `pdev`.
+            let mut dev = unsafe { Device::from_ptr(pdev) };
+            let info = Self::get_id_info(&dev);
+            let data = T::probe(&mut dev, info)?;
+            // SAFETY: `pdev` is guaranteed to be a valid, non-null pointer.
+            unsafe { bindings::platform_set_drvdata(pdev, data.into_pointer() as _) };
+            Ok(0)
+        }
+    }
+
+    extern "C" fn remove_callback(pdev: *mut bindings::platform_device) -> core::ffi::c_int {
+        from_kernel_result! {
+            // SAFETY: `pdev` is guaranteed to be a valid, non-null pointer.
+            let ptr = unsafe { bindings::platform_get_drvdata(pdev) };
+            // SAFETY:
+            //   - we allocated this pointer using `T::Data::into_pointer`,
+            //     so it is safe to turn back into a `T::Data`.
+            //   - the allocation happened in `probe`, no-one freed the memory,
+            //     `remove` is the canonical kernel location to free driver data.
+            let data = unsafe { T::Data::from_pointer(ptr) };
+            let ret = T::remove(&data);
+            <T::Data as driver::DeviceRemoval>::device_remove(&data);
+            ret?;
+            Ok(0)
+        }
+    }
+}
+
+/// Represents a platform device driver.
+pub trait Driver {
+    /// Data stored on device by driver.
+    type Data: PointerWrapper + Send + Sync + driver::DeviceRemoval = ();
+
+    /// The type holding information about each device id supported by the driver.
+    type IdInfo: 'static = ();
+
+    /// The table of device ids supported by the driver.
+    const OF_DEVICE_ID_TABLE: Option<driver::IdTable<'static, of::DeviceId, Self::IdInfo>> = None;
+
+    /// Platform driver probe.
+    ///
+    /// Called when a new platform device is added or discovered.
+    /// Implementers should attempt to initialize the device here.
+    fn probe(dev: &mut Device, id_info: Option<&Self::IdInfo>) -> Result<Self::Data>;
+
+    /// Platform driver remove.
+    ///
+    /// Called when a platform device is removed.
+    /// Implementers should prepare the device for complete removal here.
+    fn remove(_data: &Self::Data) -> Result {
+        Ok(())
+    }
+}
+
+/// A platform device.
+///
+/// # Invariants
+///
+/// The field `ptr` is non-null and valid for the lifetime of the object.
+pub struct Device {
+    ptr: *mut bindings::platform_device,
+}
+
+impl Device {
+    /// Creates a new device from the given pointer.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be non-null and valid. It must remain valid for the lifetime of the returned
+    /// instance.
+    unsafe fn from_ptr(ptr: *mut bindings::platform_device) -> Self {
+        // INVARIANT: The safety requirements of the function ensure the lifetime invariant.
+        Self { ptr }
+    }
+
+    /// Returns the resource of the given type at index `num`, if any.
+    ///
+    /// Forwards to `platform_get_resource`.
+    pub fn resource(&self, type_: u32, num: u32) -> Option<Resource> {
+        // SAFETY: By the type invariants, `self.ptr` is non-null and valid.
+        let res = unsafe { bindings::platform_get_resource(self.ptr, type_, num) };
+        // SAFETY: The resource, if any, lives as long as the platform device does.
+        unsafe { Resource::from_ptr(res) }
+    }
+
+    /// Returns the resource of the given type with the given name, if any.
+    ///
+    /// Forwards to `platform_get_resource_byname`.
+    pub fn resource_by_name(&self, type_: u32, name: &CStr) -> Option<Resource> {
+        // SAFETY: By the type invariants, `self.ptr` is non-null and valid; `name` is valid for
+        // the duration of the call.
+        let res = unsafe {
+            bindings::platform_get_resource_byname(self.ptr, type_, name.as_char_ptr())
+        };
+        // SAFETY: The resource, if any, lives as long as the platform device does.
+        unsafe { Resource::from_ptr(res) }
+    }
+
+    /// Maps the `IORESOURCE_MEM` region at index `num` into a device-managed MMIO accessor.
+    ///
+    /// The mapping is released automatically when the device is unbound. Forwards to
+    /// `platform_get_resource` followed by `devm_ioremap_resource`.
+    pub fn ioremap_resource(&self, num: u32) -> Result<IoMem> {
+        // SAFETY: By the type invariants, `self.ptr` is non-null and valid.
+        let res = unsafe {
+            bindings::platform_get_resource(self.ptr, bindings::IORESOURCE_MEM, num)
+        };
+        if res.is_null() {
+            return Err(ENODEV);
+        }
+
+        // SAFETY: `self.raw_device()` and `res` are valid for the duration of the call; the
+        // returned mapping is owned by the device core.
+        let ptr = unsafe { bindings::devm_ioremap_resource(self.raw_device(), res) };
+        let ptr = from_kernel_err_ptr(ptr)?;
+        // SAFETY: `res` is a valid resource pointer as checked above.
+        let size = unsafe { bindings::resource_size(res) } as usize;
+        // INVARIANT: `ptr` is a valid, non-null mapping owned by the device core for at least
+        // `size` bytes.
+        Ok(IoMem { ptr: ptr as *mut c_void, size })
+    }
+
+    /// Returns the IRQ number at index `num`.
+    ///
+    /// Forwards to `platform_get_irq`.
+    pub fn irq(&self, num: u32) -> Result<u32> {
+        // SAFETY: By the type invariants, `self.ptr` is non-null and valid.
+        let ret = unsafe { bindings::platform_get_irq(self.ptr, num as _) };
+        if ret < 0 {
+            return Err(Error::from_kernel_errno(ret));
+        }
+        Ok(ret as u32)
+    }
+
+    /// Registers a threaded interrupt handler for `irq`.
+    ///
+    /// `handler` runs in thread context (so it may sleep, e.g. to read controller registers over
+    /// I2C) and returns how the interrupt was handled. The interrupt is freed automatically when
+    /// the device is unbound. Forwards to `devm_request_threaded_irq`.
+    pub fn request_threaded_irq<H>(&self, irq: u32, name: &'static CStr, handler: H) -> Result
+    where
+        H: Fn() -> IrqReturn + Send + Sync + 'static,
+    {
+        let boxed = Box::try_new(handler)?;
+        let ptr = Box::into_raw(boxed);
+
+        // Register the box-free action *before* requesting the IRQ. `devm` cleanups run LIFO, so
+        // this action runs after `free_irq`, guaranteeing the handler box is released only once the
+        // interrupt can no longer fire with `ptr` as its `dev_id`. On failure here the action has
+        // already run (`devm_add_action` falls back to running it), releasing the box.
+        crate::devres::devm_add_action(self, ptr, |ptr: *mut H| {
+            // SAFETY: `ptr` was produced by `Box::into_raw` above. This action runs after the IRQ
+            // is freed, so the handler is no longer referenced.
+            drop(unsafe { Box::from_raw(ptr) });
+        })?;
+
+        // SAFETY: `self.raw_device()` is valid; the top half is left NULL so a threaded handler is
+        // created; `ptr` stays valid until the handler box is freed by the action registered above,
+        // which `devm` runs after this IRQ is freed on unbind.
+        let ret = unsafe {
+            bindings::devm_request_threaded_irq(
+                self.raw_device(),
+                irq,
+                None,
+                Some(irq_thread_trampoline::<H>),
+                bindings::IRQF_ONESHOT as _,
+                name.as_char_ptr(),
+                ptr as *mut c_void,
+            )
+        };
+        if ret < 0 {
+            // The box-free action stays registered and releases `ptr` when the device unbinds, so
+            // there is nothing to clean up here beyond reporting the error.
+            return Err(Error::from_kernel_errno(ret));
+        }
+
+        Ok(())
+    }
+}
+
+unsafe extern "C" fn irq_thread_trampoline<H>(
+    _irq: core::ffi::c_int,
+    dev_id: *mut c_void,
+) -> bindings::irqreturn_t
+where
+    H: Fn() -> IrqReturn + Send + Sync + 'static,
+{
+    // SAFETY: `dev_id` is the handler boxed in `request_threaded_irq`; it is borrowed, not taken.
+    let handler = unsafe { &*(dev_id as *const H) };
+    handler().to_raw()
+}
+
+// SAFETY: The device returned by `raw_device` is the raw platform device.
+unsafe impl device::RawDevice for Device {
+    fn raw_device(&self) -> *mut bindings::device {
+        // SAFETY: By the type invariants, we know that `self.ptr` is non-null and valid.
+        unsafe { &mut (*self.ptr).dev }
+    }
+}
+
+/// A platform device resource (memory region, IRQ, ...).
+///
+/// # Invariants
+///
+/// The field `ptr` is non-null and valid for the lifetime of the owning device.
+pub struct Resource {
+    ptr: *mut bindings::resource,
+}
+
+impl Resource {
+    /// Wraps a raw resource pointer, returning [`None`] if it is null.
+    ///
+    /// # Safety
+    ///
+    /// If non-null, `ptr` must be valid for the lifetime of the returned instance.
+    unsafe fn from_ptr(ptr: *mut bindings::resource) -> Option<Self> {
+        if ptr.is_null() {
+            None
+        } else {
+            Some(Self { ptr })
+        }
+    }
+
+    /// The start address of the resource.
+    pub fn start(&self) -> u64 {
+        // SAFETY: By the type invariants, `self.ptr` is non-null and valid.
+        unsafe { (*self.ptr).start }
+    }
+
+    /// The size of the resource in bytes.
+    pub fn size(&self) -> u64 {
+        // SAFETY: By the type invariants, `self.ptr` is non-null and valid.
+        unsafe { bindings::resource_size(self.ptr) }
+    }
+}
+
+/// A guarded, device-managed MMIO accessor.
+///
+/// # Invariants
+///
+/// The field `ptr` is a non-null mapping owned by the device core, valid for `size` bytes until
+/// the device is unbound.
+pub struct IoMem {
+    ptr: *mut c_void,
+    size: usize,
+}
+
+impl IoMem {
+    /// Reads a 32-bit value at `offset`.
+    pub fn readl(&self, offset: usize) -> u32 {
+        // SAFETY: `offset` is within bounds, and the mapping is valid by the type invariants.
+        unsafe { bindings::readl(self.at::<u32>(offset)) }
+    }
+
+    /// Writes a 32-bit `value` at `offset`.
+    pub fn writel(&self, value: u32, offset: usize) {
+        // SAFETY: `offset` is within bounds, and the mapping is valid by the type invariants.
+        unsafe { bindings::writel(value, self.at::<u32>(offset)) }
+    }
+
+    /// Reads a byte at `offset`.
+    pub fn readb(&self, offset: usize) -> u8 {
+        // SAFETY: `offset` is within bounds, and the mapping is valid by the type invariants.
+        unsafe { bindings::readb(self.at::<u8>(offset)) }
+    }
+
+    /// Writes a `value` byte at `offset`.
+    pub fn writeb(&self, value: u8, offset: usize) {
+        // SAFETY: `offset` is within bounds, and the mapping is valid by the type invariants.
+        unsafe { bindings::writeb(value, self.at::<u8>(offset)) }
+    }
+
+    /// Returns a pointer `offset` bytes into the mapping, panicking unless the full access of
+    /// `size_of::<T>()` bytes starting at `offset` fits within the mapping.
+    fn at<T>(&self, offset: usize) -> *mut c_void {
+        // Guard against width-dependent overflow past the end of the mapping (e.g. a 4-byte access
+        // at `offset == size - 1`).
+        match offset.checked_add(core::mem::size_of::<T>()) {
+            Some(end) if end <= self.size => {}
+            _ => panic!("MMIO access out of bounds"),
+        }
+        // SAFETY: The bounds check above guarantees the full access is within the mapping.
+        unsafe { self.ptr.cast::<u8>().add(offset).cast::<c_void>() }
+    }
+}
+
+// SAFETY: An MMIO mapping may be accessed from any thread; the device retains ownership.
+unsafe impl Send for IoMem {}
+// SAFETY: MMIO accessors take `&self` and perform volatile hardware accesses, safe to share.
+unsafe impl Sync for IoMem {}
+
+/// The outcome of a threaded interrupt handler.
+#[derive(Clone, Copy)]
+pub enum IrqReturn {
+    /// The interrupt was not for this device.
+    None,
+    /// The interrupt was handled by this device.
+    Handled,
+}
+
+impl IrqReturn {
+    fn to_raw(self) -> bindings::irqreturn_t {
+        match self {
+            IrqReturn::None => bindings::irqreturn_IRQ_NONE,
+            IrqReturn::Handled => bindings::irqreturn_IRQ_HANDLED,
+        }
+    }
+}
+
+/// Declares a kernel module that exposes a single platform device driver.
+///
+/// The `type` argument should be a type which implements the [`Driver`] trait. Also accepts
+/// various forms of kernel metadata.
+#[macro_export]
+macro_rules! module_platform_driver {
+    ($($f:tt)*) => {
+        $crate::module_driver!(<T>, $crate::platform::DriverAdapter<T>, { $($f)* });
+    };
+}
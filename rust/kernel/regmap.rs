@@ -0,0 +1,186 @@
+// SPDX-License-Identifier: GPL-2.0
+
+//! Register map access.
+//!
+//! C header: [`include/linux/regmap.h`](../../../../include/linux/regmap.h)
+//!
+//! Reference: <https://docs.kernel.org/driver-api/regmap.html>
+
+use core::mem::MaybeUninit;
+
+use crate::{
+    bindings,
+    error::{from_kernel_err_ptr, Result},
+    i2c,
+    to_result,
+};
+
+/// The caching policy of a register map.
+#[derive(Clone, Copy)]
+pub enum CacheType {
+    /// Don't cache anything.
+    None,
+    /// Cache using a flat table of registers.
+    Flat,
+    /// Cache using a red-black tree.
+    RbTree,
+    /// Cache using a maple tree.
+    Maple,
+}
+
+impl CacheType {
+    fn to_raw(self) -> bindings::regmap_cache_type {
+        match self {
+            CacheType::None => bindings::regmap_cache_type_REGCACHE_NONE,
+            CacheType::Flat => bindings::regmap_cache_type_REGCACHE_FLAT,
+            CacheType::RbTree => bindings::regmap_cache_type_REGCACHE_RBTREE,
+            CacheType::Maple => bindings::regmap_cache_type_REGCACHE_MAPLE,
+        }
+    }
+}
+
+/// Configuration of a register map.
+///
+/// Maps the subset of `struct regmap_config` fields that a simple register-mapped device needs.
+pub struct RegmapConfig(bindings::regmap_config);
+
+impl RegmapConfig {
+    /// Creates a new configuration with the given register and value widths (in bits).
+    pub fn new(reg_bits: i32, val_bits: i32) -> Self {
+        // SAFETY: `regmap_config` is a plain-old-data struct that is valid when zeroed.
+        let mut config: bindings::regmap_config = unsafe { core::mem::zeroed() };
+        config.reg_bits = reg_bits;
+        config.val_bits = val_bits;
+        Self(config)
+    }
+
+    /// Sets the highest register address present in the device.
+    pub fn max_register(mut self, max_register: u32) -> Self {
+        self.0.max_register = max_register;
+        self
+    }
+
+    /// Sets the caching policy of the register map.
+    pub fn cache_type(mut self, cache_type: CacheType) -> Self {
+        self.0.cache_type = cache_type.to_raw();
+        self
+    }
+
+    /// Sets the mask OR'ed into register addresses on reads.
+    pub fn read_flag_mask(mut self, mask: u64) -> Self {
+        self.0.read_flag_mask = mask;
+        self
+    }
+
+    /// Sets the mask OR'ed into register addresses on writes.
+    pub fn write_flag_mask(mut self, mask: u64) -> Self {
+        self.0.write_flag_mask = mask;
+        self
+    }
+}
+
+/// A register map.
+///
+/// # Invariants
+///
+/// The field `ptr` is non-null and owned for the lifetime of the object.
+pub struct Regmap {
+    ptr: *mut bindings::regmap,
+}
+
+impl Regmap {
+    /// Creates a register map backed by an I2C client.
+    ///
+    /// Forwards to `regmap_init_i2c`.
+    pub fn init_i2c(client: &i2c::Client, config: &RegmapConfig) -> Result<Self> {
+        // `regmap_init_i2c` is a macro that fills in the lock-class arguments; call the underlying
+        // `__regmap_init_i2c` directly, passing null for the lockdep key and name.
+        // SAFETY: `client` is valid while borrowed, and `config` is a valid configuration.
+        let ptr = unsafe {
+            from_kernel_err_ptr(bindings::__regmap_init_i2c(
+                client.raw_i2c_client(),
+                &config.0,
+                core::ptr::null_mut(),
+                core::ptr::null(),
+            ))
+        }?;
+        // INVARIANT: `regmap_init_i2c` returned a non-null, owned pointer.
+        Ok(Self { ptr })
+    }
+
+    /// Reads the value of a single register.
+    pub fn read(&self, reg: u32) -> Result<u32> {
+        let mut val = MaybeUninit::<u32>::uninit();
+        // SAFETY: By the type invariants, `self.ptr` is valid. `val` is valid for writes.
+        to_result(unsafe { bindings::regmap_read(self.ptr, reg, val.as_mut_ptr()) })?;
+        // SAFETY: `regmap_read` initialised `val` on success.
+        Ok(unsafe { val.assume_init() })
+    }
+
+    /// Writes a value to a single register.
+    pub fn write(&self, reg: u32, val: u32) -> Result {
+        // SAFETY: By the type invariants, `self.ptr` is valid.
+        to_result(unsafe { bindings::regmap_write(self.ptr, reg, val) })
+    }
+
+    /// Updates the bits of a register selected by `mask` to `val`.
+    ///
+    /// This is a read-modify-write that only issues a write when the masked bits actually change,
+    /// matching the semantics of `regmap_update_bits`.
+    pub fn update_bits(&self, reg: u32, mask: u32, val: u32) -> Result {
+        // `regmap_update_bits` is a `static inline` wrapper around `regmap_update_bits_base`; call
+        // the latter directly with no change report and neither async nor forced writes.
+        // SAFETY: By the type invariants, `self.ptr` is valid.
+        to_result(unsafe {
+            bindings::regmap_update_bits_base(
+                self.ptr,
+                reg,
+                mask,
+                val,
+                core::ptr::null_mut(),
+                false,
+                false,
+            )
+        })
+    }
+
+    /// Reads a block of consecutive registers into `vals`.
+    pub fn read_bulk(&self, reg: u32, vals: &mut [u32]) -> Result {
+        // SAFETY: By the type invariants, `self.ptr` is valid. `vals` is valid for writes of
+        // `vals.len()` elements.
+        to_result(unsafe {
+            bindings::regmap_bulk_read(
+                self.ptr,
+                reg,
+                vals.as_mut_ptr() as *mut _,
+                vals.len(),
+            )
+        })
+    }
+
+    /// Writes a block of values to consecutive registers starting at `reg`.
+    pub fn write_bulk(&self, reg: u32, vals: &[u32]) -> Result {
+        // SAFETY: By the type invariants, `self.ptr` is valid. `vals` is valid for reads of
+        // `vals.len()` elements.
+        to_result(unsafe {
+            bindings::regmap_bulk_write(
+                self.ptr,
+                reg,
+                vals.as_ptr() as *const _,
+                vals.len(),
+            )
+        })
+    }
+}
+
+impl Drop for Regmap {
+    fn drop(&mut self) {
+        // SAFETY: By the type invariants, `self.ptr` is a valid, owned register map.
+        unsafe { bindings::regmap_exit(self.ptr) };
+    }
+}
+
+// SAFETY: A `regmap` may be used from any thread; the kernel serialises accesses internally.
+unsafe impl Send for Regmap {}
+// SAFETY: `regmap` accessors take an internal lock, so shared access is safe across threads.
+unsafe impl Sync for Regmap {}
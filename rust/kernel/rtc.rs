@@ -119,6 +119,53 @@ impl RtcTime {
     }
 }
 
+/// RTC wakeup alarm.
+///
+/// Wraps a `struct rtc_wkalrm`, exposing the `enabled`/`pending` flags together with the alarm
+/// time (reusing [`RtcTime`]).
+pub struct RtcWakeAlarm {
+    ptr: *mut bindings::rtc_wkalrm,
+}
+
+impl RtcWakeAlarm {
+    /// Creates a new RTC wakeup alarm from the given pointer.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be non-null and valid. It must remain valid for the lifetime of the returned
+    /// instance.
+    unsafe fn from_ptr(ptr: *mut bindings::rtc_wkalrm) -> Self {
+        Self { ptr }
+    }
+
+    /// Whether the alarm is enabled.
+    pub fn enabled(&self) -> bool {
+        unsafe { (*self.ptr).enabled != 0 }
+    }
+
+    /// Sets whether the alarm is enabled.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        unsafe { (*self.ptr).enabled = enabled as _ }
+    }
+
+    /// Whether the alarm is pending.
+    pub fn pending(&self) -> bool {
+        unsafe { (*self.ptr).pending != 0 }
+    }
+
+    /// Sets whether the alarm is pending.
+    pub fn set_pending(&mut self, pending: bool) {
+        unsafe { (*self.ptr).pending = pending as _ }
+    }
+
+    /// The time at which the alarm is scheduled to fire.
+    pub fn time(&self) -> RtcTime {
+        // SAFETY: By the safety requirements of `from_ptr`, `self.ptr` is valid, so a pointer to
+        // its `time` field is valid for the lifetime of `self`.
+        unsafe { RtcTime::from_ptr(&mut (*self.ptr).time) }
+    }
+}
+
 /// A real time clock (RTC).
 #[vtable]
 pub trait Rtc {
@@ -133,6 +180,30 @@ pub trait Rtc {
 
     /// Sets the date and time of the RTC.
     fn set_time(_data: <Self::Data as PointerWrapper>::Borrowed<'_>, time: &RtcTime) -> Result;
+
+    /// Reads the currently programmed wakeup alarm from the RTC.
+    fn read_alarm(
+        _data: <Self::Data as PointerWrapper>::Borrowed<'_>,
+        _alarm: &mut RtcWakeAlarm,
+    ) -> Result {
+        Err(ENOTSUPP)
+    }
+
+    /// Programs the wakeup alarm of the RTC.
+    fn set_alarm(
+        _data: <Self::Data as PointerWrapper>::Borrowed<'_>,
+        _alarm: &RtcWakeAlarm,
+    ) -> Result {
+        Err(ENOTSUPP)
+    }
+
+    /// Enables or disables the alarm interrupt.
+    fn alarm_irq_enable(
+        _data: <Self::Data as PointerWrapper>::Borrowed<'_>,
+        _enabled: bool,
+    ) -> Result {
+        Err(ENOTSUPP)
+    }
 }
 
 /// A registration of a real time clock (RTC).
@@ -175,6 +246,15 @@ impl<T: Rtc> Registration<T> {
             if T::HAS_SET_TIME {
                 ops.set_time = Some(set_time_callback::<T>);
             }
+            if T::HAS_READ_ALARM {
+                ops.read_alarm = Some(read_alarm_callback::<T>);
+            }
+            if T::HAS_SET_ALARM {
+                ops.set_alarm = Some(set_alarm_callback::<T>);
+            }
+            if T::HAS_ALARM_IRQ_ENABLE {
+                ops.alarm_irq_enable = Some(alarm_irq_enable_callback::<T>);
+            }
         }
 
         let rtc = unsafe {
@@ -197,6 +277,23 @@ impl<T: Rtc> Registration<T> {
         this.parent = Some(device::Device::from_dev(parent));
         Ok(())
     }
+
+    /// Reports `num` RTC events of the given `events` mask to the kernel.
+    ///
+    /// Intended to be called from a driver's IRQ handler to signal that an alarm has fired (by
+    /// passing [`bindings::RTC_AF`] together with [`bindings::RTC_IRQF`] in `events`).
+    ///
+    /// Returns [`EINVAL`] if the registration has not been registered yet.
+    pub fn update_irq(&self, num: usize, events: u32) -> Result {
+        // `parent` is only set once `devm_rtc_register_device` has succeeded.
+        if self.parent.is_none() {
+            return Err(EINVAL);
+        }
+        let rtc = self.rtc.ok_or(EINVAL)?;
+        // SAFETY: `rtc` was allocated and registered in `register`, so it remains valid.
+        unsafe { bindings::rtc_update_irq(rtc, num as _, events as _) };
+        Ok(())
+    }
 }
 
 unsafe extern "C" fn read_time_callback<T: Rtc>(
@@ -224,3 +321,41 @@ unsafe extern "C" fn set_time_callback<T: Rtc>(
         Ok(0)
     }
 }
+
+unsafe extern "C" fn read_alarm_callback<T: Rtc>(
+    dev: *mut bindings::device,
+    alarm: *mut bindings::rtc_wkalrm,
+) -> core::ffi::c_int {
+    from_kernel_result! {
+        // SAFETY: The value stored as chip data was returned by `into_pointer` during registration.
+        let data = unsafe { T::Data::borrow(bindings::dev_get_drvdata(dev)) };
+        let mut alarm = unsafe { RtcWakeAlarm::from_ptr(alarm) };
+        T::read_alarm(data, &mut alarm)?;
+        Ok(0)
+    }
+}
+
+unsafe extern "C" fn set_alarm_callback<T: Rtc>(
+    dev: *mut bindings::device,
+    alarm: *mut bindings::rtc_wkalrm,
+) -> core::ffi::c_int {
+    from_kernel_result! {
+        // SAFETY: The value stored as chip data was returned by `into_pointer` during registration.
+        let data = unsafe { T::Data::borrow(bindings::dev_get_drvdata(dev)) };
+        let alarm = unsafe { RtcWakeAlarm::from_ptr(alarm) };
+        T::set_alarm(data, &alarm)?;
+        Ok(0)
+    }
+}
+
+unsafe extern "C" fn alarm_irq_enable_callback<T: Rtc>(
+    dev: *mut bindings::device,
+    enabled: core::ffi::c_uint,
+) -> core::ffi::c_int {
+    from_kernel_result! {
+        // SAFETY: The value stored as chip data was returned by `into_pointer` during registration.
+        let data = unsafe { T::Data::borrow(bindings::dev_get_drvdata(dev)) };
+        T::alarm_irq_enable(data, enabled != 0)?;
+        Ok(0)
+    }
+}